@@ -190,6 +190,11 @@ impl BroadcastConsumer {
 		self.catalog_latest.as_ref()
 	}
 
+	/// The ID of the current broadcast, which changes when the publisher restarts.
+	pub fn id(&self) -> Option<&str> {
+		self.current.as_deref()
+	}
+
 	/// Returns the latest catalog, or None if the channel is offline.
 	pub async fn next_catalog(&mut self) -> Result<Option<&Catalog>> {
 		loop {
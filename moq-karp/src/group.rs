@@ -73,7 +73,8 @@ impl GroupConsumer {
 				_ => (),
 			}
 
-			match self.read_frame().await {
+			// NOTE: read_frame would pop the buffered frames, so read directly from the group.
+			match self.read_frame_unbuffered().await {
 				Ok(Some(frame)) => self.buffered.push_back(frame),
 				// Otherwise block forever so we don't return from FuturesUnordered
 				_ => std::future::pending().await,
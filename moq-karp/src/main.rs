@@ -5,9 +5,11 @@ use clap::{Parser, Subcommand};
 use moq_transfork::Session;
 use url::Url;
 
-use moq_karp::{cmaf, BroadcastProducer};
+use moq_karp::{cmaf, BroadcastConsumer, BroadcastProducer};
 use moq_native::quic;
 
+mod probe;
+
 #[derive(Parser, Clone)]
 struct Config {
 	/// Listen for UDP packets on the given address.
@@ -40,6 +42,14 @@ pub enum Command {
 		///   The path is used to identify the broadcast, with the rest of the URL (ex. query/fragment) currently ignored.
 		url: String,
 	},
+
+	/// Subscribe to a broadcast and print an analysis of each track, without decoding.
+	///
+	/// This logs the catalog, any changes to the codec parameters, and a summary of every group of pictures.
+	Probe {
+		/// The URL must start with `https://` or `http://`, see `publish` for more information.
+		url: String,
+	},
 }
 
 #[tokio::main]
@@ -49,6 +59,7 @@ async fn main() -> anyhow::Result<()> {
 
 	match config.command.clone() {
 		Command::Publish { url } => publish(config, url).await,
+		Command::Probe { url } => probe(config, url).await,
 		//Command::Subscribe => subscribe(session, broadcast).await,
 	}
 }
@@ -85,6 +96,14 @@ async fn publish(config: Config, url: String) -> anyhow::Result<()> {
 	}
 }
 
+#[tracing::instrument(skip_all, fields(?url))]
+async fn probe(config: Config, url: String) -> anyhow::Result<()> {
+	let (session, path) = connect(&config, &url).await?;
+	let broadcast = BroadcastConsumer::new(session, path);
+
+	probe::run(broadcast).await
+}
+
 /*
 #[tracing::instrument("subscribe", skip_all, err, fields(?broadcast))]
 async fn subscribe(session: moq_transfork::Session, broadcast: Broadcast) -> anyhow::Result<()> {
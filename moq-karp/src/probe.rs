@@ -0,0 +1,361 @@
+use std::{collections::HashMap, time::Duration};
+
+use anyhow::Context;
+use futures::{future::BoxFuture, stream::FuturesUnordered, FutureExt, StreamExt};
+use moq_karp::{Audio, BroadcastConsumer, Catalog, Frame, Timestamp, Track, TrackConsumer, Video};
+use tokio::sync::oneshot;
+
+// How far a newer group can get ahead of the current frame before the rest of the current group is skipped.
+// This is several GOPs for most content, so only stalled groups are skipped.
+const PROBE_LATENCY: Duration = Duration::from_secs(30);
+
+pub async fn run(mut broadcast: BroadcastConsumer) -> anyhow::Result<()> {
+	// The previous catalog, used to detect parameter changes.
+	// This is kept when the broadcast goes offline, since that's when a restarted publisher can change parameters.
+	let mut previous: Option<Catalog> = None;
+
+	// The ID of the broadcast that the active tracks belong to.
+	let mut id: Option<String> = None;
+
+	// The active tracks by name, along with their catalog entry.
+	// Dropping the sender stops the track, logging the current GOP.
+	let mut video: HashMap<String, (Video, oneshot::Sender<()>)> = HashMap::new();
+	let mut audio: HashMap<String, (Audio, oneshot::Sender<()>)> = HashMap::new();
+
+	let mut tracks = FuturesUnordered::new();
+
+	loop {
+		tokio::select! {
+			res = broadcast.next_catalog() => {
+				let catalog = match res? {
+					Some(catalog) => catalog.clone(),
+					None => {
+						tracing::info!("broadcast offline");
+						video.clear();
+						audio.clear();
+						continue;
+					}
+				};
+
+				tracing::info!(catalog = %catalog.to_string_pretty()?, "catalog");
+
+				for video in &catalog.video {
+					let old = previous
+						.as_ref()
+						.and_then(|previous| previous.video.iter().find(|old| old.track == video.track));
+
+					if let Some(old) = old {
+						if old.codec != video.codec
+							|| old.description != video.description
+							|| old.resolution != video.resolution
+						{
+							tracing::warn!(
+								track = ?video.track.name,
+								old = %old.codec,
+								new = %video.codec,
+								resolution = ?video.resolution,
+								"parameters changed"
+							);
+						}
+					}
+				}
+
+				for audio in &catalog.audio {
+					let old = previous
+						.as_ref()
+						.and_then(|previous| previous.audio.iter().find(|old| old.track == audio.track));
+
+					if let Some(old) = old {
+						if old.codec != audio.codec
+							|| old.sample_rate != audio.sample_rate
+							|| old.channel_count != audio.channel_count
+						{
+							tracing::warn!(
+								track = ?audio.track.name,
+								old = %old.codec,
+								new = %audio.codec,
+								sample_rate = audio.sample_rate,
+								channel_count = audio.channel_count,
+								"parameters changed"
+							);
+						}
+					}
+				}
+
+				// A restarted publisher uses new track paths, even if the catalog is identical.
+				if id.as_deref() != broadcast.id() {
+					id = broadcast.id().map(ToString::to_string);
+					video.clear();
+					audio.clear();
+				}
+
+				// Only resubscribe to tracks that are new or have changed, so we don't reset their stats.
+				resubscribe(&broadcast, &mut video, &catalog.video, |video| &video.track, &mut tracks)?;
+				resubscribe(&broadcast, &mut audio, &catalog.audio, |audio| &audio.track, &mut tracks)?;
+
+				previous = Some(catalog);
+			},
+			Some(()) = tracks.next() => {},
+		}
+	}
+}
+
+// Stop any tracks that were removed or changed, and subscribe to any that are new or changed.
+fn resubscribe<T: Clone + PartialEq>(
+	broadcast: &BroadcastConsumer,
+	active: &mut HashMap<String, (T, oneshot::Sender<()>)>,
+	entries: &[T],
+	info: fn(&T) -> &Track,
+	tracks: &mut FuturesUnordered<BoxFuture<'static, ()>>,
+) -> anyhow::Result<()> {
+	active.retain(|name, (old, _)| entries.iter().any(|entry| info(entry).name == *name && entry == old));
+
+	for entry in entries {
+		let track = info(entry);
+		if active.contains_key(&track.name) {
+			continue;
+		}
+
+		let mut consumer = broadcast.track(track)?;
+
+		// Avoid skipping late groups so the stats aren't filtered like a player.
+		consumer.set_latency(PROBE_LATENCY);
+
+		let (stop, stopped) = oneshot::channel();
+		tracks.push(probe_track(track.name.clone(), consumer, stopped).boxed());
+		active.insert(track.name.clone(), (entry.clone(), stop));
+	}
+
+	Ok(())
+}
+
+// Track errors are not fatal; the catalog will be reloaded if the publisher restarts.
+async fn probe_track(name: String, track: TrackConsumer, stopped: oneshot::Receiver<()>) {
+	let mut gops = Gops::default();
+
+	tokio::select! {
+		res = probe_track_inner(&name, track, &mut gops) => {
+			if let Err(err) = res {
+				tracing::warn!(track = ?name, ?err, "track error");
+			}
+		},
+		// Either a message or the sender being dropped.
+		_ = stopped => tracing::debug!(track = ?name, "track stopped"),
+	}
+
+	if let Some(gop) = gops.finish() {
+		log_gop(&name, &gop);
+	}
+}
+
+async fn probe_track_inner(name: &str, mut track: TrackConsumer, gops: &mut Gops) -> anyhow::Result<()> {
+	while let Some(frame) = track.read().await? {
+		let sequence = track.sequence().context("missing group")?;
+
+		if let Some(gop) = gops.frame(sequence, &frame) {
+			log_gop(name, &gop);
+		}
+	}
+
+	tracing::info!(track = ?name, "track ended");
+
+	Ok(())
+}
+
+fn log_gop(name: &str, gop: &Gop) {
+	if gop.missing > 0 {
+		// moq-transfork only delivers the latest group, so any groups in between were never received.
+		tracing::warn!(track = ?name, sequence = gop.sequence, missing = gop.missing, "missing groups");
+	}
+
+	tracing::info!(
+		track = ?name,
+		sequence = gop.sequence,
+		start = ?gop.start,
+		duration = ?gop.duration,
+		frames = gop.frames,
+		bytes = gop.bytes,
+		bitrate = ?gop.bitrate(),
+		"group"
+	);
+}
+
+// A summary of a single group of pictures, starting at a keyframe.
+#[derive(Debug, Clone, PartialEq)]
+struct Gop {
+	// The sequence number of the MoQ group.
+	sequence: u64,
+
+	// The number of groups missing between the previous GOP and this one.
+	missing: u64,
+
+	start: Timestamp,
+	duration: Duration,
+	frames: usize,
+	bytes: usize,
+}
+
+impl Gop {
+	// Returns None if the duration is zero, ex. a GOP with a single frame.
+	fn bitrate(&self) -> Option<u64> {
+		match self.duration.as_secs_f64() {
+			secs if secs > 0.0 => Some((self.bytes as f64 * 8.0 / secs) as u64),
+			_ => None,
+		}
+	}
+}
+
+// Splits frames into GOPs without performing any I/O.
+#[derive(Default)]
+struct Gops {
+	current: Option<Gop>,
+
+	// The largest timestamp in the current GOP.
+	end: Timestamp,
+
+	// The sequence number of the most recent GOP.
+	sequence: Option<u64>,
+}
+
+impl Gops {
+	// Add a frame from the given group, returning the previous GOP if this frame starts a new one.
+	fn frame(&mut self, sequence: u64, frame: &Frame) -> Option<Gop> {
+		let mut done = None;
+
+		if frame.keyframe {
+			// The GOP lasts until the next keyframe.
+			done = self.finish_at(frame.timestamp);
+
+			let missing = match self.sequence {
+				Some(prev) if sequence > prev => sequence - prev - 1,
+				_ => 0,
+			};
+
+			self.sequence = Some(sequence);
+			self.end = frame.timestamp;
+			self.current = Some(Gop {
+				sequence,
+				missing,
+				start: frame.timestamp,
+				duration: Duration::ZERO,
+				frames: 0,
+				bytes: 0,
+			});
+		}
+
+		// Skip any frames until the first keyframe.
+		if let Some(gop) = self.current.as_mut() {
+			self.end = self.end.max(frame.timestamp);
+			gop.frames += 1;
+			gop.bytes += frame.payload.len();
+		}
+
+		done
+	}
+
+	// Finish the current GOP, using the largest timestamp as the end.
+	fn finish(&mut self) -> Option<Gop> {
+		self.finish_at(self.end)
+	}
+
+	fn finish_at(&mut self, end: Timestamp) -> Option<Gop> {
+		let mut gop = self.current.take()?;
+		gop.duration = end.saturating_sub(gop.start);
+		Some(gop)
+	}
+}
+
+#[cfg(test)]
+mod test {
+	use bytes::Bytes;
+
+	use super::*;
+
+	fn frame(millis: u64, keyframe: bool, size: usize) -> Frame {
+		Frame {
+			timestamp: Timestamp::from_millis(millis),
+			keyframe,
+			payload: Bytes::from(vec![0u8; size]),
+		}
+	}
+
+	#[test]
+	fn split() {
+		let mut gops = Gops::default();
+
+		assert_eq!(gops.frame(0, &frame(0, true, 1000)), None);
+		assert_eq!(gops.frame(0, &frame(500, false, 100)), None);
+		assert_eq!(gops.frame(0, &frame(1500, false, 150)), None);
+
+		let gop = gops.frame(1, &frame(2000, true, 800)).expect("missing gop");
+		assert_eq!(
+			gop,
+			Gop {
+				sequence: 0,
+				missing: 0,
+				start: Timestamp::ZERO,
+				duration: Duration::from_secs(2),
+				frames: 3,
+				bytes: 1250,
+			}
+		);
+		assert_eq!(gop.bitrate(), Some(5000));
+
+		assert_eq!(gops.frame(1, &frame(2500, false, 200)), None);
+
+		// The final GOP ends at the largest timestamp.
+		let gop = gops.finish().expect("missing gop");
+		assert_eq!(gop.sequence, 1);
+		assert_eq!(gop.duration, Duration::from_millis(500));
+		assert_eq!(gop.frames, 2);
+		assert_eq!(gop.bytes, 1000);
+		assert_eq!(gop.bitrate(), Some(16000));
+
+		assert_eq!(gops.finish(), None);
+	}
+
+	#[test]
+	fn before_keyframe() {
+		let mut gops = Gops::default();
+
+		// Frames before the first keyframe are ignored.
+		assert_eq!(gops.frame(0, &frame(0, false, 100)), None);
+		assert_eq!(gops.frame(0, &frame(33, false, 100)), None);
+		assert_eq!(gops.finish(), None);
+
+		assert_eq!(gops.frame(1, &frame(66, true, 500)), None);
+		let gop = gops.finish().expect("missing gop");
+		assert_eq!(gop.frames, 1);
+		assert_eq!(gop.bytes, 500);
+	}
+
+	#[test]
+	fn zero_duration() {
+		let mut gops = Gops::default();
+
+		assert_eq!(gops.frame(0, &frame(1000, true, 500)), None);
+
+		let gop = gops.finish().expect("missing gop");
+		assert_eq!(gop.duration, Duration::ZERO);
+		assert_eq!(gop.bitrate(), None);
+	}
+
+	#[test]
+	fn missing() {
+		let mut gops = Gops::default();
+
+		assert_eq!(gops.frame(5, &frame(0, true, 100)), None);
+
+		let gop = gops.frame(6, &frame(1000, true, 100)).expect("missing gop");
+		assert_eq!(gop.missing, 0);
+
+		// Groups 7 and 8 were never received.
+		let gop = gops.frame(9, &frame(4000, true, 100)).expect("missing gop");
+		assert_eq!(gop.sequence, 6);
+		assert_eq!(gop.missing, 0);
+
+		let gop = gops.finish().expect("missing gop");
+		assert_eq!(gop.sequence, 9);
+		assert_eq!(gop.missing, 2);
+	}
+}
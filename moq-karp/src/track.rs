@@ -102,7 +102,10 @@ impl TrackConsumer {
 
 					match res? {
 						// Got the next frame.
-						Some(frame) => return Ok(Some(frame)),
+						Some(frame) => {
+							self.max_timestamp = self.max_timestamp.max(frame.timestamp);
+							return Ok(Some(frame));
+						}
 						None => {
 							// Group ended cleanly, instantly move to the next group.
 							self.current = self.pending.pop_front();
@@ -143,6 +146,11 @@ impl TrackConsumer {
 		}
 	}
 
+	/// The sequence number of the group containing the last frame returned by [Self::read].
+	pub fn sequence(&self) -> Option<u64> {
+		self.current.as_ref().map(|group| group.sequence)
+	}
+
 	pub fn set_latency(&mut self, max: std::time::Duration) {
 		self.latency = max;
 	}
@@ -151,3 +159,55 @@ impl TrackConsumer {
 		self.track.closed().await.map_err(Into::into)
 	}
 }
+
+#[cfg(test)]
+mod test {
+	use super::*;
+
+	use futures::FutureExt;
+	use std::time::Duration;
+
+	fn write(group: &mut moq_transfork::GroupProducer, timestamp: Timestamp) {
+		let mut buf = BytesMut::new();
+		(timestamp.as_micros() as u64).encode(&mut buf);
+		buf.extend_from_slice(b"payload");
+		group.write_frame(buf.freeze());
+	}
+
+	fn read(consumer: &mut TrackConsumer) -> Option<Frame> {
+		consumer.read().now_or_never()?.expect("read failed")
+	}
+
+	#[test]
+	fn latency() {
+		let (mut producer, consumer) = moq_transfork::Track::new("test").produce();
+		let mut consumer = TrackConsumer::new(consumer);
+		consumer.set_latency(Duration::from_secs(1));
+
+		// Start well past the latency, like when joining a live broadcast.
+		let mut group0 = producer.create_group(0);
+		write(&mut group0, Duration::from_secs(100));
+
+		let frame = read(&mut consumer).expect("would have blocked");
+		assert_eq!(frame.timestamp, Duration::from_secs(100));
+		assert!(frame.keyframe);
+
+		// A newer group within the latency should not cause the current group to be skipped.
+		let mut group1 = producer.create_group(1);
+		write(&mut group1, Duration::from_millis(100_500));
+		assert!(read(&mut consumer).is_none(), "skipped the current group");
+
+		write(&mut group0, Duration::from_millis(100_033));
+		let frame = read(&mut consumer).expect("would have blocked");
+		assert_eq!(frame.timestamp, Duration::from_millis(100_033));
+		assert!(!frame.keyframe);
+
+		// A newer group beyond the latency should cause every older group to be skipped.
+		let mut group2 = producer.create_group(2);
+		write(&mut group2, Duration::from_secs(102));
+
+		let frame = read(&mut consumer).expect("would have blocked");
+		assert_eq!(frame.timestamp, Duration::from_secs(102));
+		assert!(frame.keyframe);
+	}
+}
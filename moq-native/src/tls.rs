@@ -43,6 +43,7 @@ pub struct Args {
 
 	/// Generate a self-signed certificate for the provided hostnames (comma separated).
 	///
+	/// IP addresses are also accepted and added as IP subject alternative names.
 	/// This is useful for local development and testing.
 	/// This can be combined with the `/fingerprint` endpoint for clients to fetch the fingerprint.
 	#[arg(long = "tls-self-sign", value_delimiter = ',')]
@@ -53,6 +54,8 @@ pub struct Args {
 pub struct Config {
 	pub client: rustls::ClientConfig,
 	pub server: Option<rustls::ServerConfig>,
+
+	/// The SHA-256 fingerprints of every served certificate, generated or loaded, which are also logged on startup.
 	pub fingerprints: Vec<String>,
 }
 
@@ -120,6 +123,11 @@ impl Args {
 
 		let fingerprints = serve.fingerprints();
 
+		// Log the fingerprints so they can be pinned by clients without fetching `/fingerprint`.
+		for fingerprint in &fingerprints {
+			tracing::info!(%fingerprint, "certificate fingerprint");
+		}
+
 		// Create the TLS configuration we'll use as a server (relay <- browser)
 		let server = if !serve.list.is_empty() {
 			Some(